@@ -1,7 +1,9 @@
 use std::io::SeekFrom;
 use std::num::TryFromIntError;
+use std::str::FromStr;
 use std::{fmt::Display, ops::RangeBounds};
 
+use bytes::Bytes;
 use snafu::prelude::*;
 
 pub const BYTES: &str = "bytes";
@@ -38,11 +40,44 @@ impl ByteRange {
             ByteRange::Suffix(n) => Some(*n),
         }
     }
-}
 
-impl Display for ByteRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{BYTES}="))?;
+    /// Resolves this range against a known resource length, returning the
+    /// concrete inclusive `(start, end)` byte offsets, or [None] if the range is
+    /// not satisfiable (an HTTP 416 case).
+    pub fn to_satisfiable_range(&self, total_length: usize) -> Option<(usize, usize)> {
+        match self {
+            ByteRange::Range { start, end } => {
+                if *start >= total_length {
+                    return None;
+                }
+                let end = (*end).unwrap_or(total_length - 1).min(total_length - 1);
+                (*start <= end).then_some((*start, end))
+            }
+            ByteRange::Suffix(n) => {
+                if total_length == 0 || *n == 0 {
+                    return None;
+                }
+                Some((total_length.saturating_sub(*n), total_length - 1))
+            }
+        }
+    }
+
+    /// Carves out the sub-slice of `data` described by this range, resolving it
+    /// against `data.len()`. Returns [None] when the range is unsatisfiable.
+    pub fn slice<'a>(&self, data: &'a [u8]) -> Option<&'a [u8]> {
+        let (start, end) = self.to_satisfiable_range(data.len())?;
+        Some(&data[start..=end])
+    }
+
+    /// As [ByteRange::slice], but returns an owned (cheaply cloned) [Bytes].
+    pub fn slice_bytes(&self, data: &Bytes) -> Option<Bytes> {
+        let (start, end) = self.to_satisfiable_range(data.len())?;
+        Some(data.slice(start..=end))
+    }
+
+    /// Writes the bare range specifier (e.g. `0-1`, `1000-`, `-100`) without the
+    /// `bytes=` unit prefix, so it can be reused when formatting a set of ranges.
+    fn fmt_spec(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ByteRange::Range { start, end } => {
                 f.write_fmt(format_args!("{start}-"))?;
@@ -56,6 +91,196 @@ impl Display for ByteRange {
     }
 }
 
+impl Display for ByteRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{BYTES}="))?;
+        self.fmt_spec(f)
+    }
+}
+
+/// A set of [ByteRange] specifiers, as permitted by a single RFC 7233 `Range`
+/// header (e.g. `bytes=0-1,30-40,-100`).
+///
+/// Its [Display] emits a single `bytes=` prefix followed by the comma-joined
+/// specifiers, allowing callers to express coalesced multi-part reads in one
+/// request.
+///
+/// Note that an empty set renders as a bare `bytes=`, which is not a valid
+/// `Range` value and will not round-trip through [FromStr]; callers are expected
+/// to hold at least one specifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteRanges(pub Vec<ByteRange>);
+
+impl ByteRanges {
+    /// Creates a set from an iterator of individual ranges.
+    pub fn new(ranges: impl IntoIterator<Item = ByteRange>) -> Self {
+        Self(ranges.into_iter().collect())
+    }
+}
+
+impl<R: RangeBounds<usize>> FromIterator<R> for ByteRanges {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        Self(iter.into_iter().map(ByteRange::from).collect())
+    }
+}
+
+impl Display for ByteRanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{BYTES}="))?;
+        for (i, range) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            range.fmt_spec(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when parsing a [ByteRange] or [ByteRanges] from a header value.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum InvalidByteRange {
+    #[snafu(display("Invalid range unit, expected `{BYTES}=`: {value:?}"))]
+    InvalidUnit { value: String },
+
+    #[snafu(display("Invalid content-range unit, expected `{BYTES} `: {value:?}"))]
+    InvalidContentRangeUnit { value: String },
+
+    #[snafu(display("Malformed range specifier: {value:?}"))]
+    MalformedSpec { value: String },
+
+    #[snafu(display("Range bound is not a valid integer: {value:?}"))]
+    InvalidInt {
+        value: String,
+        source: std::num::ParseIntError,
+    },
+
+    #[snafu(display("Range start {start} is greater than end {end}"))]
+    StartAfterEnd { start: usize, end: usize },
+}
+
+/// Parses a single range specifier (without the `bytes=` prefix), e.g. `0-1999`,
+/// `1000-` or `-2000`.
+fn parse_spec(spec: &str) -> Result<ByteRange, InvalidByteRange> {
+    if let Some(n) = spec.strip_prefix('-') {
+        let n = n.parse().context(InvalidIntSnafu { value: spec })?;
+        return Ok(ByteRange::Suffix(n));
+    }
+    let (start, end) = spec
+        .split_once('-')
+        .context(MalformedSpecSnafu { value: spec })?;
+    let start = start.parse().context(InvalidIntSnafu { value: spec })?;
+    if end.is_empty() {
+        return Ok(ByteRange::Range { start, end: None });
+    }
+    let end = end.parse().context(InvalidIntSnafu { value: spec })?;
+    if start > end {
+        return StartAfterEndSnafu { start, end }.fail();
+    }
+    Ok(ByteRange::Range {
+        start,
+        end: Some(end),
+    })
+}
+
+/// Strips the `bytes=` unit prefix from a `Range` header value.
+fn strip_unit(s: &str) -> Result<&str, InvalidByteRange> {
+    s.strip_prefix(&format!("{BYTES}="))
+        .context(InvalidUnitSnafu { value: s })
+}
+
+impl FromStr for ByteRange {
+    type Err = InvalidByteRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_spec(strip_unit(s)?)
+    }
+}
+
+impl FromStr for ByteRanges {
+    type Err = InvalidByteRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = strip_unit(s)?
+            .split(',')
+            .map(|spec| parse_spec(spec.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(ranges))
+    }
+}
+
+/// A parsed `Content-Range` response header, as returned by a store answering a
+/// ranged GET (e.g. `bytes 200-1000/67589`, or `bytes */67589` when the request
+/// was unsatisfiable).
+///
+/// Invariant: `start` and `end` are always both `Some` or both `None` (the
+/// latter only for the unsatisfiable `*` form); [FromStr] never produces a mix.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ContentRange {
+    /// Offset of the first byte of the satisfied range, 0-based inclusive.
+    /// [None] when the server reported the range as unsatisfiable (`*`).
+    pub start: Option<usize>,
+    /// Offset of the last byte of the satisfied range, 0-based inclusive.
+    /// [None] when the server reported the range as unsatisfiable (`*`).
+    pub end: Option<usize>,
+    /// The complete length of the resource, or [None] when it is unknown (`*`).
+    pub complete_length: Option<usize>,
+}
+
+impl ContentRange {
+    /// Returns the [ByteRange] the server satisfied together with the complete
+    /// length of the resource, or [None] if the range was unsatisfiable.
+    pub fn satisfied(&self) -> Option<(ByteRange, Option<usize>)> {
+        let (start, end) = (self.start?, self.end?);
+        Some((
+            ByteRange::Range {
+                start,
+                end: Some(end),
+            },
+            self.complete_length,
+        ))
+    }
+}
+
+impl FromStr for ContentRange {
+    type Err = InvalidByteRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .strip_prefix(BYTES)
+            .and_then(|r| r.strip_prefix(' '))
+            .context(InvalidContentRangeUnitSnafu { value: s })?;
+        let (range, length) = rest
+            .split_once('/')
+            .context(MalformedSpecSnafu { value: s })?;
+
+        let complete_length = match length {
+            "*" => None,
+            n => Some(n.parse().context(InvalidIntSnafu { value: s })?),
+        };
+
+        let (start, end) = match range {
+            "*" => (None, None),
+            r => {
+                let (start, end) = r.split_once('-').context(MalformedSpecSnafu { value: s })?;
+                let start = start.parse().context(InvalidIntSnafu { value: s })?;
+                let end = end.parse().context(InvalidIntSnafu { value: s })?;
+                if start > end {
+                    return StartAfterEndSnafu { start, end }.fail();
+                }
+                (Some(start), Some(end))
+            }
+        };
+
+        Ok(Self {
+            start,
+            end,
+            complete_length,
+        })
+    }
+}
+
 impl<T: RangeBounds<usize>> From<T> for ByteRange {
     fn from(value: T) -> Self {
         use std::ops::Bound::*;
@@ -84,3 +309,274 @@ impl TryInto<SeekFrom> for &ByteRange {
 
     type Error = TryFromIntError;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_byte_range() {
+        assert_eq!(
+            ByteRange::Range {
+                start: 0,
+                end: Some(1)
+            }
+            .to_string(),
+            "bytes=0-1"
+        );
+        assert_eq!(
+            ByteRange::Range {
+                start: 1000,
+                end: None
+            }
+            .to_string(),
+            "bytes=1000-"
+        );
+        assert_eq!(ByteRange::Suffix(100).to_string(), "bytes=-100");
+    }
+
+    #[test]
+    fn display_byte_ranges() {
+        let ranges = ByteRanges(vec![
+            ByteRange::Range {
+                start: 0,
+                end: Some(1),
+            },
+            ByteRange::Range {
+                start: 30,
+                end: Some(40),
+            },
+            ByteRange::Suffix(100),
+        ]);
+        assert_eq!(ranges.to_string(), "bytes=0-1,30-40,-100");
+    }
+
+    #[test]
+    fn display_empty_byte_ranges_is_bare_unit() {
+        // Documented behaviour: an empty set renders as a bare `bytes=`.
+        assert_eq!(ByteRanges(vec![]).to_string(), "bytes=");
+    }
+
+    #[test]
+    fn parse_byte_range() {
+        assert_eq!(
+            "bytes=1000-".parse::<ByteRange>().unwrap(),
+            ByteRange::Range {
+                start: 1000,
+                end: None
+            }
+        );
+        assert_eq!(
+            "bytes=0-1999".parse::<ByteRange>().unwrap(),
+            ByteRange::Range {
+                start: 0,
+                end: Some(1999)
+            }
+        );
+        assert_eq!(
+            "bytes=-2000".parse::<ByteRange>().unwrap(),
+            ByteRange::Suffix(2000)
+        );
+    }
+
+    #[test]
+    fn parse_byte_ranges() {
+        assert_eq!(
+            "bytes=0-1,30-40,-100".parse::<ByteRanges>().unwrap(),
+            ByteRanges(vec![
+                ByteRange::Range {
+                    start: 0,
+                    end: Some(1)
+                },
+                ByteRange::Range {
+                    start: 30,
+                    end: Some(40)
+                },
+                ByteRange::Suffix(100),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_byte_range_errors() {
+        assert!(matches!(
+            "items=0-1".parse::<ByteRange>(),
+            Err(InvalidByteRange::InvalidUnit { .. })
+        ));
+        assert!(matches!(
+            "bytes=1-0".parse::<ByteRange>(),
+            Err(InvalidByteRange::StartAfterEnd { start: 1, end: 0 })
+        ));
+        assert!(matches!(
+            "bytes=a-b".parse::<ByteRange>(),
+            Err(InvalidByteRange::InvalidInt { .. })
+        ));
+    }
+
+    #[test]
+    fn byte_range_display_from_str_round_trip() {
+        for range in [
+            ByteRange::Range {
+                start: 0,
+                end: Some(1999),
+            },
+            ByteRange::Range {
+                start: 1000,
+                end: None,
+            },
+            ByteRange::Suffix(2000),
+        ] {
+            assert_eq!(range.to_string().parse::<ByteRange>().unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn parse_content_range() {
+        let cr = "bytes 200-1000/67589".parse::<ContentRange>().unwrap();
+        assert_eq!(
+            cr,
+            ContentRange {
+                start: Some(200),
+                end: Some(1000),
+                complete_length: Some(67589)
+            }
+        );
+        assert_eq!(
+            cr.satisfied(),
+            Some((
+                ByteRange::Range {
+                    start: 200,
+                    end: Some(1000)
+                },
+                Some(67589)
+            ))
+        );
+
+        let unknown = "bytes 200-1000/*".parse::<ContentRange>().unwrap();
+        assert_eq!(
+            unknown,
+            ContentRange {
+                start: Some(200),
+                end: Some(1000),
+                complete_length: None
+            }
+        );
+        assert_eq!(
+            unknown.satisfied(),
+            Some((
+                ByteRange::Range {
+                    start: 200,
+                    end: Some(1000)
+                },
+                None
+            ))
+        );
+
+        let unsatisfiable = "bytes */67589".parse::<ContentRange>().unwrap();
+        assert_eq!(
+            unsatisfiable,
+            ContentRange {
+                start: None,
+                end: None,
+                complete_length: Some(67589)
+            }
+        );
+        assert_eq!(unsatisfiable.satisfied(), None);
+    }
+
+    #[test]
+    fn parse_content_range_bad_unit() {
+        assert!(matches!(
+            "foo 0-1/10".parse::<ContentRange>(),
+            Err(InvalidByteRange::InvalidContentRangeUnit { .. })
+        ));
+    }
+
+    #[test]
+    fn satisfiable_range_bounded() {
+        let r = ByteRange::Range {
+            start: 10,
+            end: Some(20),
+        };
+        assert_eq!(r.to_satisfiable_range(100), Some((10, 20)));
+        // end clamped to total_length - 1
+        assert_eq!(r.to_satisfiable_range(15), Some((10, 14)));
+        // start beyond the resource
+        assert_eq!(r.to_satisfiable_range(10), None);
+        assert_eq!(r.to_satisfiable_range(5), None);
+    }
+
+    #[test]
+    fn satisfiable_range_open_ended() {
+        let r = ByteRange::Range {
+            start: 10,
+            end: None,
+        };
+        assert_eq!(r.to_satisfiable_range(100), Some((10, 99)));
+        assert_eq!(r.to_satisfiable_range(11), Some((10, 10)));
+        assert_eq!(r.to_satisfiable_range(0), None);
+    }
+
+    #[test]
+    fn satisfiable_range_suffix() {
+        assert_eq!(
+            ByteRange::Suffix(20).to_satisfiable_range(100),
+            Some((80, 99))
+        );
+        // oversized suffix is clamped to the whole resource
+        assert_eq!(
+            ByteRange::Suffix(200).to_satisfiable_range(100),
+            Some((0, 99))
+        );
+        // zero-length suffix and zero-length resource are unsatisfiable
+        assert_eq!(ByteRange::Suffix(0).to_satisfiable_range(100), None);
+        assert_eq!(ByteRange::Suffix(20).to_satisfiable_range(0), None);
+    }
+
+    #[test]
+    fn slice_buffer() {
+        let data: &[u8] = b"0123456789";
+        assert_eq!(
+            ByteRange::Range {
+                start: 2,
+                end: Some(4)
+            }
+            .slice(data),
+            Some(&data[2..=4])
+        );
+        assert_eq!(ByteRange::Suffix(3).slice(data), Some(&data[7..=9]));
+        assert_eq!(
+            ByteRange::Range {
+                start: 20,
+                end: None
+            }
+            .slice(data),
+            None
+        );
+    }
+
+    #[test]
+    fn slice_bytes_buffer() {
+        let data = Bytes::from_static(b"0123456789");
+        assert_eq!(
+            ByteRange::Range {
+                start: 2,
+                end: Some(4)
+            }
+            .slice_bytes(&data),
+            Some(Bytes::from_static(b"234"))
+        );
+        assert_eq!(
+            ByteRange::Suffix(3).slice_bytes(&data),
+            Some(Bytes::from_static(b"789"))
+        );
+        assert_eq!(
+            ByteRange::Range {
+                start: 20,
+                end: None
+            }
+            .slice_bytes(&data),
+            None
+        );
+    }
+}